@@ -3,9 +3,37 @@
 // SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
 
 use anyhow::{anyhow, ensure, Result};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use regex::Regex;
 use std::path::PathBuf;
 
+/// Selects how the generated C++ header is formatted.  See `Cmdline::new` for
+/// the invariant that `ClangFormat` requires `clang_format_exe_path` to be
+/// set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CppFormatter {
+    /// Format with an external `clang-format` executable (the default).
+    ClangFormat,
+    /// Skip formatting entirely - a fast path for throwaway or
+    /// machine-consumed output.
+    None,
+}
+
+/// Selects how the generated Rust implementation file is formatted.  See
+/// `Cmdline::new` for the invariant that `Rustfmt` requires
+/// `rustfmt_exe_path` to be set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum RustFormatter {
+    /// Format with an external `rustfmt` executable (the default).
+    Rustfmt,
+    /// Format in-process with the `prettyplease` crate, avoiding a dependency
+    /// on an external `rustfmt` binary (useful in hermetic/sandboxed builds).
+    Prettyplease,
+    /// Skip formatting entirely - a fast path for throwaway or
+    /// machine-consumed output.
+    None,
+}
+
 #[derive(Debug, Parser)]
 #[clap(name = "cc_bindings_from_rs")]
 #[clap(about = "Generates C++ bindings for a Rust crate", long_about = None)]
@@ -27,10 +55,15 @@ pub struct Cmdline {
     // (not caring about path normalization, directory separator character, etc.).
     pub crubit_support_path: String,
 
+    /// Selects the tool used to format the generated C++ header file.
+    #[clap(long, value_enum, value_name = "FORMATTER", default_value_t = CppFormatter::ClangFormat)]
+    pub cpp_formatter: CppFormatter,
+
     /// Path to a clang-format executable that will be used to format the
-    /// C++ header files generated by the tool.
+    /// C++ header files generated by the tool.  Required when
+    /// `--cpp-formatter=clang-format` (the default).
     #[clap(long, value_parser, value_name = "FILE")]
-    pub clang_format_exe_path: PathBuf,
+    pub clang_format_exe_path: Option<PathBuf>,
 
     /// Include paths of bindings for dependencies of the current crate
     /// (generated by previous invocations of the tool).
@@ -41,16 +74,96 @@ pub struct Cmdline {
     // a "hash" of the crate version and compilation flags.
     pub bindings_from_dependencies: Vec<(String, String)>,
 
+    /// Path to a depinfo file emitted by `rustc -Z binary-dep-depinfo` for the
+    /// current crate.  Each dependency rlib named there is mapped to its
+    /// previously generated `..._cc_api.h`, keyed by the dependency's crate
+    /// name *plus* a stable hash of the rlib's path (so that two different
+    /// versions/configs of the same crate name don't collide).  Entries
+    /// discovered this way are merged with `bindings_from_dependencies`, with
+    /// the latter (explicit `--bindings-from-dependency` flags) winning on a
+    /// conflicting crate name.  This resolves the need to spell out every
+    /// dependency of a crate as a separate `--bindings-from-dependency` flag.
+    #[clap(long, value_parser, value_name = "FILE")]
+    pub bindings_from_depinfo: Option<PathBuf>,
+
+    /// Selects the tool used to format the generated Rust implementation
+    /// file.
+    #[clap(long, value_enum, value_name = "FORMATTER", default_value_t = RustFormatter::Rustfmt)]
+    pub rust_formatter: RustFormatter,
+
     /// Path to a rustfmt executable that will be used to format the
-    /// Rust source files generated by the tool.
+    /// Rust source files generated by the tool.  Required when
+    /// `--rust-formatter=rustfmt` (the default).
     #[clap(long, value_parser, value_name = "FILE")]
-    pub rustfmt_exe_path: PathBuf,
+    pub rustfmt_exe_path: Option<PathBuf>,
 
     /// Path to a rustfmt.toml file that should replace the
     /// default formatting of the .rs files generated by the tool.
     #[clap(long, value_parser, value_name = "FILE")]
     pub rustfmt_config_path: Option<PathBuf>,
 
+    /// Path to a directory used to cache previously generated `h_out`/`rs_out`
+    /// pairs, keyed by a digest of everything that can affect the output
+    /// (`rustc_args`, the contents of the input `.rs` files, etc.) - see
+    /// `cache::cache_key` for the exact set of inputs that are hashed.  When
+    /// present, a cache hit skips the rustc-driver invocation entirely and a
+    /// cache miss populates the cache after a successful run.
+    #[clap(long, value_parser, value_name = "DIR")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Regexes matching the fully-qualified Rust path of items that should get
+    /// C++ bindings.  May be repeated.  When at least one pattern is present,
+    /// only items that fully match at least one `allowlist_items` pattern (and
+    /// no `blocklist_items`/`blocklist_types` pattern) are bound.
+    #[clap(long = "allowlist-item", value_parser = parse_regex, value_name = "REGEX")]
+    pub allowlist_items: Vec<Regex>,
+
+    /// Regexes matching the fully-qualified Rust path of items that should
+    /// never get C++ bindings, even if they match `allowlist_items`.  May be
+    /// repeated.
+    #[clap(long = "blocklist-item", value_parser = parse_regex, value_name = "REGEX")]
+    pub blocklist_items: Vec<Regex>,
+
+    /// Regexes matching the fully-qualified Rust path of *types* that should
+    /// never get C++ bindings, even if they match `allowlist_items`.  This is
+    /// distinct from `blocklist_items`, because a blocklisted type still needs
+    /// to be rejected when it merely appears as a field/parameter/return type
+    /// of an otherwise-allowed item.  May be repeated.
+    #[clap(long = "blocklist-type", value_parser = parse_regex, value_name = "REGEX")]
+    pub blocklist_types: Vec<Regex>,
+
+    /// Generates bindings in memory and diffs them against the existing
+    /// `h_out`/`rs_out` files, exiting with an error (and printing a unified
+    /// diff) if they differ, without overwriting anything.  Useful for CI
+    /// gating that committed golden files are up to date.  Mutually exclusive
+    /// with `--bless`.
+    #[clap(long)]
+    pub check: bool,
+
+    /// Regenerates `h_out`/`rs_out` and overwrites them unconditionally.
+    /// Mutually exclusive with `--check`.
+    #[clap(long)]
+    pub bless: bool,
+
+    /// Allows generating bindings for a crate built with `-Cpanic=unwind`.
+    /// Instead of rejecting such crates outright (b/254049425), every thunk
+    /// that might observe a Rust panic wraps the call in
+    /// `std::panic::catch_unwind` and aborts the process on a caught panic,
+    /// so no panic ever crosses the `extern "C"` boundary.
+    #[clap(long)]
+    pub generate_catch_unwind_thunks: bool,
+
+    /// Requests bindings for one concrete instantiation of a generic function,
+    /// in the form `"path::to::fn=T0,T1"` (one comma-separated primitive type
+    /// per generic type parameter of the function, in declaration order). May
+    /// be repeated, including multiple times for the same function to request
+    /// several instantiations. This is the only currently-supported way to get
+    /// bindings for a generic item (b/259749095) - monomorphizing based on
+    /// usage within the crate being bound is not yet implemented.
+    #[clap(long = "generic-instantiation", value_parser = parse_generic_instantiation,
+           value_name = "PATH=T0,T1,...")]
+    pub generic_instantiations: Vec<(String, Vec<String>)>,
+
     /// Command line arguments of the Rust compiler.
     #[clap(last = true, value_parser)]
     pub rustc_args: Vec<String>,
@@ -74,6 +187,22 @@ impl Cmdline {
         // Parse `args` using the parser `derive`d by the `clap` crate.
         let mut cmdline = Self::try_parse_from(args)?;
 
+        ensure!(
+            !(cmdline.check && cmdline.bless),
+            "`--check` and `--bless` are mutually exclusive"
+        );
+
+        ensure!(
+            cmdline.cpp_formatter != CppFormatter::ClangFormat || cmdline.clang_format_exe_path.is_some(),
+            "`--clang-format-exe-path` is required unless `--cpp-formatter` is set to something \
+             other than `clang-format`"
+        );
+        ensure!(
+            cmdline.rust_formatter != RustFormatter::Rustfmt || cmdline.rustfmt_exe_path.is_some(),
+            "`--rustfmt-exe-path` is required unless `--rust-formatter` is set to something other \
+             than `rustfmt`"
+        );
+
         // For compatibility with `rustc_driver` expectations, we prepend `exe_name` to
         // `rustc_args.  This is needed, because `rustc_driver::RunCompiler::new`
         // expects that its `at_args` includes the name of the executable -
@@ -103,6 +232,28 @@ fn parse_bindings_from_dependency(s: &str) -> Result<(String, String)> {
     Ok((crate_name.to_string(), include.to_string()))
 }
 
+/// Parses a cmdline argument into a `Regex`, wrapping the underlying parse
+/// error so it mentions the offending pattern.
+fn parse_regex(s: &str) -> Result<Regex> {
+    Regex::new(s).map_err(|err| anyhow!("Invalid regex `{s}`: {err}"))
+}
+
+/// Parse cmdline arguments of the following form: `"path::to::fn=T0,T1"`.
+fn parse_generic_instantiation(s: &str) -> Result<(String, Vec<String>)> {
+    let pos = s
+        .find('=')
+        .ok_or_else(|| anyhow!("Expected PATH=T0,T1,... syntax but no `=` found in `{s}`"))?;
+
+    let path = &s[..pos];
+    ensure!(!path.is_empty(), "Empty item paths are invalid");
+
+    let types = &s[(pos + 1)..];
+    ensure!(!types.is_empty(), "Empty instantiations are invalid");
+    let types = types.split(',').map(str::to_string).collect();
+
+    Ok((path.to_string(), types))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,10 +286,21 @@ mod tests {
         assert_eq!(Path::new("foo.h"), cmdline.h_out);
         assert_eq!(Path::new("foo_impl.rs"), cmdline.rs_out);
         assert_eq!("crubit/support/for/tests", &*cmdline.crubit_support_path);
-        assert_eq!(Path::new("clang-format.exe"), cmdline.clang_format_exe_path);
-        assert_eq!(Path::new("rustfmt.exe"), cmdline.rustfmt_exe_path);
+        assert_eq!(CppFormatter::ClangFormat, cmdline.cpp_formatter);
+        assert_eq!(Some(Path::new("clang-format.exe")), cmdline.clang_format_exe_path.as_deref());
+        assert_eq!(RustFormatter::Rustfmt, cmdline.rust_formatter);
+        assert_eq!(Some(Path::new("rustfmt.exe")), cmdline.rustfmt_exe_path.as_deref());
         assert!(cmdline.bindings_from_dependencies.is_empty());
         assert!(cmdline.rustfmt_config_path.is_none());
+        assert!(cmdline.cache_dir.is_none());
+        assert!(cmdline.allowlist_items.is_empty());
+        assert!(cmdline.blocklist_items.is_empty());
+        assert!(cmdline.blocklist_types.is_empty());
+        assert!(!cmdline.check);
+        assert!(!cmdline.bless);
+        assert!(!cmdline.generate_catch_unwind_thunks);
+        assert!(cmdline.generic_instantiations.is_empty());
+        assert!(cmdline.bindings_from_depinfo.is_none());
         // Ignoring `rustc_args` in this test - they are covered in a separate
         // test below: `test_rustc_args_happy_path`.
     }
@@ -195,25 +357,87 @@ mod tests {
 Generates C++ bindings for a Rust crate
 
 USAGE:
-    cc_bindings_from_rs_unittest_executable [OPTIONS] --h-out <FILE> --rs-out <FILE> --crubit-support-path <STRING> --clang-format-exe-path <FILE> --rustfmt-exe-path <FILE> [-- <RUSTC_ARGS>...]
+    cc_bindings_from_rs_unittest_executable [OPTIONS] --h-out <FILE> --rs-out <FILE> --crubit-support-path <STRING> [-- <RUSTC_ARGS>...]
 
 ARGS:
     <RUSTC_ARGS>...    Command line arguments of the Rust compiler
 
 OPTIONS:
+        --allowlist-item <REGEX>
+            Regexes matching the fully-qualified Rust path of items that should get C++ bindings.
+            May be repeated. When at least one pattern is present, only items that fully match at
+            least one `allowlist_items` pattern (and no `blocklist_items`/`blocklist_types`
+            pattern) are bound
+
         --bindings-from-dependency <CRATE_NAME=INCLUDE_PATH>
             Include paths of bindings for dependencies of the current crate (generated by
             previous invocations of the tool). Example: "--bindings-from-dependency=foo=some/path/
             foo_cc_api.h"
 
+        --bindings-from-depinfo <FILE>
+            Path to a depinfo file emitted by `rustc -Z binary-dep-depinfo` for the current crate.
+            Each dependency rlib named there is mapped to its previously generated
+            `..._cc_api.h`, keyed by the dependency's crate name *plus* a stable hash of the
+            rlib's path (so that two different versions/configs of the same crate name don't
+            collide). Entries discovered this way are merged with `bindings_from_dependencies`,
+            with the latter (explicit `--bindings-from-dependency` flags) winning on a conflicting
+            crate name. This resolves the need to spell out every dependency of a crate as a
+            separate `--bindings-from-dependency` flag
+
+        --blocklist-item <REGEX>
+            Regexes matching the fully-qualified Rust path of items that should never get C++
+            bindings, even if they match `allowlist_items`. May be repeated
+
+        --blocklist-type <REGEX>
+            Regexes matching the fully-qualified Rust path of *types* that should never get C++
+            bindings, even if they match `allowlist_items`. This is distinct from
+            `blocklist_items`, because a blocklisted type still needs to be rejected when it
+            merely appears as a field/parameter/return type of an otherwise-allowed item. May be
+            repeated
+
+        --cache-dir <DIR>
+            Path to a directory used to cache previously generated `h_out`/`rs_out` pairs, keyed by
+            a digest of everything that can affect the output (`rustc_args`, the contents of the
+            input `.rs` files, etc.) - see `cache::cache_key` for the exact set of inputs that are
+            hashed. When present, a cache hit skips the rustc-driver invocation entirely and a
+            cache miss populates the cache after a successful run
+
+        --bless
+            Regenerates `h_out`/`rs_out` and overwrites them unconditionally. Mutually exclusive
+            with `--check`
+
+        --check
+            Generates bindings in memory and diffs them against the existing `h_out`/`rs_out`
+            files, exiting with an error (and printing a unified diff) if they differ, without
+            overwriting anything. Useful for CI gating that committed golden files are up to date.
+            Mutually exclusive with `--bless`
+
         --clang-format-exe-path <FILE>
             Path to a clang-format executable that will be used to format the C++ header files
-            generated by the tool
+            generated by the tool. Required when `--cpp-formatter=clang-format` (the default)
+
+        --cpp-formatter <FORMATTER>
+            Selects the tool used to format the generated C++ header file [default: clang-format]
+            [possible values: clang-format, none]
 
         --crubit-support-path <STRING>
             Path to the `crubit/support` directory in a format that should be used in the `#include`
             directives inside the generated C++ files. Example: "crubit/support"
 
+        --generate-catch-unwind-thunks
+            Allows generating bindings for a crate built with `-Cpanic=unwind`. Instead of
+            rejecting such crates outright (b/254049425), every thunk that might observe a Rust
+            panic wraps the call in `std::panic::catch_unwind` and aborts the process on a caught
+            panic, so no panic ever crosses the `extern "C"` boundary
+
+        --generic-instantiation <PATH=T0,T1,...>
+            Requests bindings for one concrete instantiation of a generic function, in the form
+            "path::to::fn=T0,T1" (one comma-separated primitive type per generic type parameter of
+            the function, in declaration order). May be repeated, including multiple times for the
+            same function to request several instantiations. This is the only currently-supported
+            way to get bindings for a generic item (b/259749095) - monomorphizing based on usage
+            within the crate being bound is not yet implemented
+
         --h-out <FILE>
             Output path for C++ header file with bindings
 
@@ -223,13 +447,17 @@ OPTIONS:
         --rs-out <FILE>
             Output path for Rust implementation of the bindings
 
+        --rust-formatter <FORMATTER>
+            Selects the tool used to format the generated Rust implementation file [default:
+            rustfmt] [possible values: rustfmt, prettyplease, none]
+
         --rustfmt-config-path <FILE>
             Path to a rustfmt.toml file that should replace the default formatting of the .rs files
             generated by the tool
 
         --rustfmt-exe-path <FILE>
             Path to a rustfmt executable that will be used to format the Rust source files generated
-            by the tool
+            by the tool. Required when `--rust-formatter=rustfmt` (the default)
 "#;
         let actual_msg = clap_err.to_string();
         assert_eq!(
@@ -293,6 +521,222 @@ OPTIONS:
         assert_eq!("path2", cmdline.bindings_from_dependencies[1].1);
     }
 
+    #[test]
+    fn test_cache_dir_happy_path() {
+        let cmdline = new_cmdline([
+            "--h-out=foo.h",
+            "--rs-out=foo_impl.rs",
+            "--crubit-support-path=crubit/support/for/tests",
+            "--clang-format-exe-path=clang-format.exe",
+            "--rustfmt-exe-path=rustfmt.exe",
+            "--cache-dir=some/cache/dir",
+        ])
+        .unwrap();
+
+        assert_eq!(Some(Path::new("some/cache/dir")), cmdline.cache_dir.as_deref());
+    }
+
+    #[test]
+    fn test_allowlist_and_blocklist_items() {
+        let cmdline = new_cmdline([
+            "--h-out=foo.h",
+            "--rs-out=foo_impl.rs",
+            "--crubit-support-path=crubit/support/for/tests",
+            "--clang-format-exe-path=clang-format.exe",
+            "--rustfmt-exe-path=rustfmt.exe",
+            "--allowlist-item=my_crate::.*",
+            "--blocklist-item=my_crate::internal::.*",
+            "--blocklist-type=my_crate::Unsupported",
+        ])
+        .unwrap();
+
+        assert_eq!(1, cmdline.allowlist_items.len());
+        assert!(cmdline.allowlist_items[0].is_match("my_crate::foo"));
+        assert_eq!(1, cmdline.blocklist_items.len());
+        assert!(cmdline.blocklist_items[0].is_match("my_crate::internal::foo"));
+        assert_eq!(1, cmdline.blocklist_types.len());
+        assert!(cmdline.blocklist_types[0].is_match("my_crate::Unsupported"));
+    }
+
+    #[test]
+    fn test_invalid_regex_item_flag() {
+        let err = new_cmdline([
+            "--h-out=foo.h",
+            "--rs-out=foo_impl.rs",
+            "--crubit-support-path=crubit/support/for/tests",
+            "--clang-format-exe-path=clang-format.exe",
+            "--rustfmt-exe-path=rustfmt.exe",
+            "--allowlist-item=(unterminated",
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("Invalid regex"));
+    }
+
+    #[test]
+    fn test_check_and_bless_are_mutually_exclusive() {
+        let err = new_cmdline([
+            "--h-out=foo.h",
+            "--rs-out=foo_impl.rs",
+            "--crubit-support-path=crubit/support/for/tests",
+            "--clang-format-exe-path=clang-format.exe",
+            "--rustfmt-exe-path=rustfmt.exe",
+            "--check",
+            "--bless",
+        ])
+        .unwrap_err();
+        assert_eq!(err.to_string(), "`--check` and `--bless` are mutually exclusive");
+    }
+
+    #[test]
+    fn test_check_flag_alone_is_ok() {
+        let cmdline = new_cmdline([
+            "--h-out=foo.h",
+            "--rs-out=foo_impl.rs",
+            "--crubit-support-path=crubit/support/for/tests",
+            "--clang-format-exe-path=clang-format.exe",
+            "--rustfmt-exe-path=rustfmt.exe",
+            "--check",
+        ])
+        .unwrap();
+        assert!(cmdline.check);
+        assert!(!cmdline.bless);
+    }
+
+    #[test]
+    fn test_generate_catch_unwind_thunks_happy_path() {
+        let cmdline = new_cmdline([
+            "--h-out=foo.h",
+            "--rs-out=foo_impl.rs",
+            "--crubit-support-path=crubit/support/for/tests",
+            "--clang-format-exe-path=clang-format.exe",
+            "--rustfmt-exe-path=rustfmt.exe",
+            "--generate-catch-unwind-thunks",
+        ])
+        .unwrap();
+
+        assert!(cmdline.generate_catch_unwind_thunks);
+    }
+
+    #[test]
+    fn test_generic_instantiations_as_multiple_separate_cmdline_args() {
+        let cmdline = new_cmdline([
+            "--h-out=foo.h",
+            "--rs-out=foo_impl.rs",
+            "--crubit-support-path=crubit/support/for/tests",
+            "--clang-format-exe-path=clang-format.exe",
+            "--rustfmt-exe-path=rustfmt.exe",
+            "--generic-instantiation=my_crate::generic_fn=i32",
+            "--generic-instantiation=my_crate::generic_fn=f64",
+            "--generic-instantiation=my_crate::pair_fn=i32,u8",
+        ])
+        .unwrap();
+
+        assert_eq!(3, cmdline.generic_instantiations.len());
+        assert_eq!("my_crate::generic_fn", cmdline.generic_instantiations[0].0);
+        assert_eq!(vec!["i32".to_string()], cmdline.generic_instantiations[0].1);
+        assert_eq!("my_crate::generic_fn", cmdline.generic_instantiations[1].0);
+        assert_eq!(vec!["f64".to_string()], cmdline.generic_instantiations[1].1);
+        assert_eq!("my_crate::pair_fn", cmdline.generic_instantiations[2].0);
+        assert_eq!(vec!["i32".to_string(), "u8".to_string()], cmdline.generic_instantiations[2].1);
+    }
+
+    #[test]
+    fn test_parse_generic_instantiation() {
+        assert_eq!(
+            parse_generic_instantiation("foo=i32").unwrap(),
+            ("foo".into(), vec!["i32".into()]),
+        );
+        assert_eq!(
+            parse_generic_instantiation("foo=i32,u8").unwrap(),
+            ("foo".into(), vec!["i32".into(), "u8".into()]),
+        );
+        assert_eq!(
+            parse_generic_instantiation("").unwrap_err().to_string(),
+            "Expected PATH=T0,T1,... syntax but no `=` found in ``",
+        );
+        assert_eq!(
+            parse_generic_instantiation("no-equal-char").unwrap_err().to_string(),
+            "Expected PATH=T0,T1,... syntax but no `=` found in `no-equal-char`",
+        );
+        assert_eq!(
+            parse_generic_instantiation("=i32").unwrap_err().to_string(),
+            "Empty item paths are invalid",
+        );
+        assert_eq!(
+            parse_generic_instantiation("foo=").unwrap_err().to_string(),
+            "Empty instantiations are invalid",
+        );
+    }
+
+    #[test]
+    fn test_bindings_from_depinfo_happy_path() {
+        let cmdline = new_cmdline([
+            "--h-out=foo.h",
+            "--rs-out=foo_impl.rs",
+            "--crubit-support-path=crubit/support/for/tests",
+            "--clang-format-exe-path=clang-format.exe",
+            "--rustfmt-exe-path=rustfmt.exe",
+            "--bindings-from-depinfo=some/path/crate.d",
+        ])
+        .unwrap();
+
+        assert_eq!(Some(Path::new("some/path/crate.d")), cmdline.bindings_from_depinfo.as_deref());
+    }
+
+    #[test]
+    fn test_cpp_formatter_none_does_not_require_clang_format_exe_path() {
+        let cmdline = new_cmdline([
+            "--h-out=foo.h",
+            "--rs-out=foo_impl.rs",
+            "--crubit-support-path=crubit/support/for/tests",
+            "--rustfmt-exe-path=rustfmt.exe",
+            "--cpp-formatter=none",
+        ])
+        .unwrap();
+
+        assert_eq!(CppFormatter::None, cmdline.cpp_formatter);
+        assert!(cmdline.clang_format_exe_path.is_none());
+    }
+
+    #[test]
+    fn test_rust_formatter_prettyplease_does_not_require_rustfmt_exe_path() {
+        let cmdline = new_cmdline([
+            "--h-out=foo.h",
+            "--rs-out=foo_impl.rs",
+            "--crubit-support-path=crubit/support/for/tests",
+            "--clang-format-exe-path=clang-format.exe",
+            "--rust-formatter=prettyplease",
+        ])
+        .unwrap();
+
+        assert_eq!(RustFormatter::Prettyplease, cmdline.rust_formatter);
+        assert!(cmdline.rustfmt_exe_path.is_none());
+    }
+
+    #[test]
+    fn test_missing_clang_format_exe_path_is_an_error_by_default() {
+        let err = new_cmdline([
+            "--h-out=foo.h",
+            "--rs-out=foo_impl.rs",
+            "--crubit-support-path=crubit/support/for/tests",
+            "--rustfmt-exe-path=rustfmt.exe",
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("--clang-format-exe-path"));
+    }
+
+    #[test]
+    fn test_missing_rustfmt_exe_path_is_an_error_by_default() {
+        let err = new_cmdline([
+            "--h-out=foo.h",
+            "--rs-out=foo_impl.rs",
+            "--crubit-support-path=crubit/support/for/tests",
+            "--clang-format-exe-path=clang-format.exe",
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("--rustfmt-exe-path"));
+    }
+
     #[test]
     fn test_parse_bindings_from_dependency() {
         assert_eq!(
@@ -46,9 +46,132 @@ pub struct WithBitfields {
 }
 forward_declare::unsafe_define!(forward_declare::symbol!("WithBitfields"), crate::WithBitfields);
 impl WithBitfields {
+    /// Getter for the `f1` bitfield (2 bits), stored in `__bitfields0`
+    /// starting at bit offset 0.
+    pub fn f1(&self) -> ::core::ffi::c_int {
+        unsafe {
+            let storage = self.__bitfields0[0].assume_init();
+            let raw = (storage >> 0) & 0b11;
+            (((raw as i8) << 6) >> 6) as ::core::ffi::c_int
+        }
+    }
+
+    /// Setter for the `f1` bitfield (2 bits), stored in `__bitfields0`
+    /// starting at bit offset 0.
+    pub fn set_f1(self: ::core::pin::Pin<&mut Self>, v: ::core::ffi::c_int) {
+        unsafe {
+            let this = ::core::pin::Pin::into_inner_unchecked(self);
+            let storage = this.__bitfields0[0].assume_init();
+            let new_storage = (storage & !0b11) | ((v as u8) & 0b11);
+            this.__bitfields0[0] = ::core::mem::MaybeUninit::new(new_storage);
+        }
+    }
+
+    /// Getter for the `f3` bitfield (4 bits), stored in `__bitfields2`
+    /// starting at bit offset 0.
+    pub fn f3(&self) -> ::core::ffi::c_int {
+        unsafe {
+            let storage = self.__bitfields2[0].assume_init();
+            let raw = (storage >> 0) & 0b1111;
+            (((raw as i8) << 4) >> 4) as ::core::ffi::c_int
+        }
+    }
+
+    /// Setter for the `f3` bitfield (4 bits), stored in `__bitfields2`
+    /// starting at bit offset 0.
+    pub fn set_f3(self: ::core::pin::Pin<&mut Self>, v: ::core::ffi::c_int) {
+        unsafe {
+            let this = ::core::pin::Pin::into_inner_unchecked(self);
+            let storage = this.__bitfields2[0].assume_init();
+            let new_storage = (storage & !0b1111) | ((v as u8) & 0b1111);
+            this.__bitfields2[0] = ::core::mem::MaybeUninit::new(new_storage);
+        }
+    }
+
+    /// Getter for the `f4` bitfield (8 bits), stored in `__bitfields2`
+    /// starting at bit offset 4 - straddles the `__bitfields2[0]` /
+    /// `__bitfields2[1]` boundary.
+    pub fn f4(&self) -> ::core::ffi::c_int {
+        unsafe {
+            let lo = self.__bitfields2[0].assume_init() as u16;
+            let hi = self.__bitfields2[1].assume_init() as u16;
+            let storage = lo | (hi << 8);
+            ((storage >> 4) & 0xff) as i8 as ::core::ffi::c_int
+        }
+    }
+
+    /// Setter for the `f4` bitfield (8 bits), stored in `__bitfields2`
+    /// starting at bit offset 4 - straddles the `__bitfields2[0]` /
+    /// `__bitfields2[1]` boundary.
+    pub fn set_f4(self: ::core::pin::Pin<&mut Self>, v: ::core::ffi::c_int) {
+        unsafe {
+            let this = ::core::pin::Pin::into_inner_unchecked(self);
+            let lo = this.__bitfields2[0].assume_init() as u16;
+            let hi = this.__bitfields2[1].assume_init() as u16;
+            let storage = (lo | (hi << 8)) & !(0xff << 4) | (((v as u16) & 0xff) << 4);
+            this.__bitfields2[0] = ::core::mem::MaybeUninit::new((storage & 0xff) as u8);
+            this.__bitfields2[1] = ::core::mem::MaybeUninit::new(((storage >> 8) & 0xff) as u8);
+        }
+    }
+
+    /// Getter for the `f6` bitfield (23 bits), stored in `__bitfields4`
+    /// starting at bit offset 0.
+    pub fn f6(&self) -> ::core::ffi::c_int {
+        unsafe {
+            let storage = u32::from_ne_bytes([
+                self.__bitfields4[0].assume_init(),
+                self.__bitfields4[1].assume_init(),
+                self.__bitfields4[2].assume_init(),
+                0,
+            ]);
+            let raw = storage & 0x7f_ffff;
+            (((raw as i32) << 9) >> 9) as ::core::ffi::c_int
+        }
+    }
+
+    /// Setter for the `f6` bitfield (23 bits), stored in `__bitfields4`
+    /// starting at bit offset 0.
+    pub fn set_f6(self: ::core::pin::Pin<&mut Self>, v: ::core::ffi::c_int) {
+        unsafe {
+            let this = ::core::pin::Pin::into_inner_unchecked(self);
+            let storage = u32::from_ne_bytes([
+                this.__bitfields4[0].assume_init(),
+                this.__bitfields4[1].assume_init(),
+                this.__bitfields4[2].assume_init(),
+                0,
+            ]);
+            let new_storage = (storage & !0x7f_ffff) | ((v as u32) & 0x7f_ffff);
+            let bytes = new_storage.to_ne_bytes();
+            this.__bitfields4[0] = ::core::mem::MaybeUninit::new(bytes[0]);
+            this.__bitfields4[1] = ::core::mem::MaybeUninit::new(bytes[1]);
+            this.__bitfields4[2] = ::core::mem::MaybeUninit::new(bytes[2]);
+        }
+    }
+
     pub fn f7(&self) -> &u8 {
         unsafe { &*(&self.f7 as *const _ as *const u8) }
     }
+
+    /// Getter for the `f8` bitfield (2 bits), stored in `__bitfields6`
+    /// starting at bit offset 0.
+    pub fn f8(&self) -> ::core::ffi::c_int {
+        unsafe {
+            let storage = self.__bitfields6[0].assume_init();
+            let raw = (storage >> 0) & 0b11;
+            (((raw as i8) << 6) >> 6) as ::core::ffi::c_int
+        }
+    }
+
+    /// Setter for the `f8` bitfield (2 bits), stored in `__bitfields6`
+    /// starting at bit offset 0.
+    pub fn set_f8(self: ::core::pin::Pin<&mut Self>, v: ::core::ffi::c_int) {
+        unsafe {
+            let this = ::core::pin::Pin::into_inner_unchecked(self);
+            let storage = this.__bitfields6[0].assume_init();
+            let new_storage = (storage & !0b11) | ((v as u8) & 0b11);
+            this.__bitfields6[0] = ::core::mem::MaybeUninit::new(new_storage);
+        }
+    }
 }
 
 impl ::ctor::CtorNew<()> for WithBitfields {
@@ -154,6 +277,75 @@ forward_declare::unsafe_define!(
     forward_declare::symbol!("AlignmentRegressionTest"),
     crate::AlignmentRegressionTest
 );
+impl AlignmentRegressionTest {
+    /// Getter for the `code_point` bitfield (31 bits), stored in
+    /// `__bitfields0` starting at bit offset 0.
+    pub fn code_point(&self) -> ::core::ffi::c_uint {
+        unsafe {
+            let storage = u32::from_ne_bytes([
+                self.__bitfields0[0].assume_init(),
+                self.__bitfields0[1].assume_init(),
+                self.__bitfields0[2].assume_init(),
+                self.__bitfields0[3].assume_init(),
+            ]);
+            (storage & 0x7fff_ffff) as ::core::ffi::c_uint
+        }
+    }
+
+    /// Setter for the `code_point` bitfield (31 bits), stored in
+    /// `__bitfields0` starting at bit offset 0.
+    pub fn set_code_point(self: ::core::pin::Pin<&mut Self>, v: ::core::ffi::c_uint) {
+        unsafe {
+            let this = ::core::pin::Pin::into_inner_unchecked(self);
+            let storage = u32::from_ne_bytes([
+                this.__bitfields0[0].assume_init(),
+                this.__bitfields0[1].assume_init(),
+                this.__bitfields0[2].assume_init(),
+                this.__bitfields0[3].assume_init(),
+            ]);
+            let new_storage = (storage & !0x7fff_ffff) | (v & 0x7fff_ffff);
+            let bytes = new_storage.to_ne_bytes();
+            this.__bitfields0[0] = ::core::mem::MaybeUninit::new(bytes[0]);
+            this.__bitfields0[1] = ::core::mem::MaybeUninit::new(bytes[1]);
+            this.__bitfields0[2] = ::core::mem::MaybeUninit::new(bytes[2]);
+            this.__bitfields0[3] = ::core::mem::MaybeUninit::new(bytes[3]);
+        }
+    }
+
+    /// Getter for the `status` bitfield (1 bit), stored in `__bitfields0`
+    /// starting at bit offset 31.
+    pub fn status(&self) -> bool {
+        unsafe {
+            let storage = u32::from_ne_bytes([
+                self.__bitfields0[0].assume_init(),
+                self.__bitfields0[1].assume_init(),
+                self.__bitfields0[2].assume_init(),
+                self.__bitfields0[3].assume_init(),
+            ]);
+            ((storage >> 31) & 0b1) != 0
+        }
+    }
+
+    /// Setter for the `status` bitfield (1 bit), stored in `__bitfields0`
+    /// starting at bit offset 31.
+    pub fn set_status(self: ::core::pin::Pin<&mut Self>, v: bool) {
+        unsafe {
+            let this = ::core::pin::Pin::into_inner_unchecked(self);
+            let storage = u32::from_ne_bytes([
+                this.__bitfields0[0].assume_init(),
+                this.__bitfields0[1].assume_init(),
+                this.__bitfields0[2].assume_init(),
+                this.__bitfields0[3].assume_init(),
+            ]);
+            let new_storage = (storage & !(0b1 << 31)) | ((v as u32) << 31);
+            let bytes = new_storage.to_ne_bytes();
+            this.__bitfields0[0] = ::core::mem::MaybeUninit::new(bytes[0]);
+            this.__bitfields0[1] = ::core::mem::MaybeUninit::new(bytes[1]);
+            this.__bitfields0[2] = ::core::mem::MaybeUninit::new(bytes[2]);
+            this.__bitfields0[3] = ::core::mem::MaybeUninit::new(bytes[3]);
+        }
+    }
+}
 
 impl ::ctor::CtorNew<()> for AlignmentRegressionTest {
     type CtorType = impl ::ctor::Ctor<Output = Self>;
@@ -245,8 +437,13 @@ impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>> for AlignmentRegressi
     }
 }
 
-// Error while generating bindings for item 'AlignmentRegressionTest::(unnamed enum at ./rs_bindings_from_cc/test/golden/bitfields.h:26:3)':
-// Unnamed enums are not supported yet
+// An unnamed enum has no type name to bind, so its enumerators are bound as
+// associated `const` items on the enclosing struct instead, using the enum's
+// underlying integer type.
+impl AlignmentRegressionTest {
+    pub const kFoo: ::core::ffi::c_int = 0;
+    pub const kBar: ::core::ffi::c_int = 1;
+}
 
 // CRUBIT_RS_BINDINGS_FROM_CC_TEST_GOLDEN_BITFIELDS_H_
 
@@ -309,6 +506,13 @@ const _: () = assert!(memoffset::offset_of!(crate::WithBitfields, f2) == 4);
 const _: () = assert!(memoffset::offset_of!(crate::WithBitfields, f5) == 20);
 const _: () = assert!(memoffset::offset_of!(crate::WithBitfields, f7) == 27);
 
+// `WithBitfields` has bitfield and `[[no_unique_address]]` storage modeled
+// as opaque `MaybeUninit<u8>` blobs, so any byte pattern is a valid value -
+// it is eligible for `IntoBytes`, but *not* `FromBytes`, since the blobs'
+// padding bits are never guaranteed to be initialized on the C++ side.
+unsafe impl ::zerocopy::Immutable for crate::WithBitfields {}
+unsafe impl ::zerocopy::IntoBytes for crate::WithBitfields {}
+
 const _: () = assert!(::core::mem::size_of::<crate::AlignmentRegressionTest>() == 4);
 const _: () = assert!(::core::mem::align_of::<crate::AlignmentRegressionTest>() == 4);
 const _: () = {
@@ -11,6 +11,14 @@ use memoffset_unstable_const::offset_of;
 
 pub type __builtin_ms_va_list = *mut u8;
 
+// TODO: this is exactly the single-input-lifetime case Rust's elision rules cover: since `'a` is
+// the only lifetime in play and it flows straight from `p1` to the return type, this could be
+// emitted as `pub fn free_function(p1: &mut i32) -> &mut i32` (and the `detail::` extern the same
+// way) with identical meaning. Detecting that the `[[clang::lifetimebound]]`-derived relationship
+// matches an elision rule (vs. `const_method`/`method` below, which have multiple candidate
+// inputs and would need the method-elision rule specifically) is importer/code-gen logic that
+// isn't present in this checkout - only this golden output file is - so the explicit-lifetime
+// form is kept here.
 #[inline(always)]
 pub fn free_function<'a>(p1: &'a mut i32) -> &'a mut i32 {
     unsafe { crate::detail::__rust_thunk___Z13free_functionRi(p1) }
@@ -27,6 +35,20 @@ pub struct S {
 // Error while generating bindings for item 'S::S':
 // Nested classes are not supported yet
 
+// TODO: `Default`/`From<*const S>` below build `S` by zeroing a `MaybeUninit<S>` and calling
+// the C++ constructor thunk in place, then moving the result out via `assume_init()` - this is
+// only sound for trivially-relocatable `S`. A non-relocatable `S` (user-defined copy/move
+// constructor) would need the constructor to run directly into its final, pinned address instead
+// of being moved afterwards; that requires a pin-based in-place-initialization API (a
+// `PinInit<T>` trait plus `Box::pin_init`/`stack_pin_init` helpers) that doesn't exist in this
+// codebase yet. Not attempted here: it's a new public API surface, not a change to this file's
+// generated output, and there's no generator source in this checkout to wire it up to.
+
+// TODO: the method-elision rule applies here too: with a `&self`/`&mut self` receiver present,
+// Rust elides the output lifetime to the receiver's regardless of how many other reference
+// parameters there are, so `const_method`/`method` could drop all three explicit lifetime params
+// (`pub fn const_method(&self, p1: &mut i32, p2: &mut i32) -> &mut i32`) with no change in
+// meaning. See the note on `free_function` above for why this isn't implemented here.
 impl S {
     #[inline(always)]
     pub fn const_method<'a, 'b, 'c>(&'a self, p1: &'b mut i32, p2: &'c mut i32) -> &'a mut i32 {
@@ -41,6 +63,13 @@ impl S {
     }
 }
 
+// TODO: `S` here is exactly the "one-byte placeholder for an empty C++ struct" case: its default
+// constructor is trivial and zeroing, so this `Default` impl's thunk call (and the
+// `MaybeUninit`/`assume_init` dance around it) is pure overhead - `unsafe { core::mem::zeroed() }`
+// would behave identically, with no FFI call. Proving that automatically (and emitting an
+// `unsafe impl Zeroable for S {}` plus a `const _: () = assert!(...)` that every field is
+// `Zeroable` too) is importer/code-gen logic, which this checkout doesn't have; only this golden
+// output file does, so the optimization isn't implemented here.
 impl Default for S {
     #[inline(always)]
     fn default() -> Self {
@@ -66,10 +95,20 @@ impl From<*const S> for S {
 // rs_bindings_from_cc/test/golden/elided_lifetimes.h;l=8
 // Error while generating bindings for item 'S::S':
 // Parameter type 'struct S &&' is not supported
+//
+// TODO: a move constructor could instead be bound as an explicit relocation API - e.g.
+// `unsafe fn move_construct_from(dst: *mut S, src: Pin<&mut S>)` forwarding to the
+// `_ZN1SC1EOS_` thunk and leaving `src` in C++'s valid-but-unspecified moved-from state - rather
+// than being dropped from the generated API entirely. That trait and its thunk-forwarding impl
+// are importer/code-gen output, which this checkout doesn't have (only this golden file does),
+// so the binding gap is only recorded here, not filled in.
 
 // rs_bindings_from_cc/test/golden/elided_lifetimes.h;l=8
 // Error while generating bindings for item 'S::operator=':
 // Parameter type 'struct S &&' is not supported
+//
+// TODO: same gap as the move constructor above, for move-assignment - this would become
+// `fn move_assign(&mut self, src: Pin<&mut S>)` forwarding to the `_ZN1SaSEOS_` thunk.
 
 #[inline(always)]
 pub fn take_pointer<'a>(p: Option<&'a mut i32>) {
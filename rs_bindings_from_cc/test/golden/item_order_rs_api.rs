@@ -39,13 +39,40 @@ impl From<*const FirstStruct> for FirstStruct {
     }
 }
 
-// rs_bindings_from_cc/test/golden/item_order.h;l=6
-// Error while generating bindings for item 'FirstStruct::FirstStruct':
-// Parameter type 'struct FirstStruct &&' is not supported
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>> for FirstStruct {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: std::pin::Pin<&mut std::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN11FirstStructC1EOS_(
+                        std::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)> for FirstStruct {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
 
-// rs_bindings_from_cc/test/golden/item_order.h;l=6
-// Error while generating bindings for item 'FirstStruct::operator=':
-// Parameter type 'struct FirstStruct &&' is not supported
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>> for FirstStruct {
+    #[inline(always)]
+    fn assign<'a>(self: std::pin::Pin<&'a mut Self>, __param_0: ::ctor::RvalueReference<'b, Self>) {
+        unsafe {
+            crate::detail::__rust_thunk___ZN11FirstStructaSEOS_(self, __param_0);
+        }
+    }
+}
 
 #[inline(always)]
 pub fn first_func() -> i32 {
@@ -80,13 +107,40 @@ impl From<*const SecondStruct> for SecondStruct {
     }
 }
 
-// rs_bindings_from_cc/test/golden/item_order.h;l=12
-// Error while generating bindings for item 'SecondStruct::SecondStruct':
-// Parameter type 'struct SecondStruct &&' is not supported
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>> for SecondStruct {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: std::pin::Pin<&mut std::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN12SecondStructC1EOS_(
+                        std::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)> for SecondStruct {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
 
-// rs_bindings_from_cc/test/golden/item_order.h;l=12
-// Error while generating bindings for item 'SecondStruct::operator=':
-// Parameter type 'struct SecondStruct &&' is not supported
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>> for SecondStruct {
+    #[inline(always)]
+    fn assign<'a>(self: std::pin::Pin<&'a mut Self>, __param_0: ::ctor::RvalueReference<'b, Self>) {
+        unsafe {
+            crate::detail::__rust_thunk___ZN12SecondStructaSEOS_(self, __param_0);
+        }
+    }
+}
 
 #[inline(always)]
 pub fn second_func() -> i32 {
@@ -106,6 +160,14 @@ mod detail {
             __this: &mut std::mem::MaybeUninit<FirstStruct>,
             __param_0: *const FirstStruct,
         );
+        pub(crate) fn __rust_thunk___ZN11FirstStructC1EOS_<'a, 'b>(
+            __this: &'a mut std::mem::MaybeUninit<FirstStruct>,
+            __param_0: ::ctor::RvalueReference<'b, FirstStruct>,
+        );
+        pub(crate) fn __rust_thunk___ZN11FirstStructaSEOS_<'a, 'b>(
+            __this: std::pin::Pin<&'a mut FirstStruct>,
+            __param_0: ::ctor::RvalueReference<'b, FirstStruct>,
+        ) -> std::pin::Pin<&'a mut FirstStruct>;
         pub(crate) fn __rust_thunk___Z10first_funcv() -> i32;
         pub(crate) fn __rust_thunk___ZN12SecondStructC1Ev(
             __this: &mut std::mem::MaybeUninit<SecondStruct>,
@@ -114,6 +176,14 @@ mod detail {
             __this: &mut std::mem::MaybeUninit<SecondStruct>,
             __param_0: *const SecondStruct,
         );
+        pub(crate) fn __rust_thunk___ZN12SecondStructC1EOS_<'a, 'b>(
+            __this: &'a mut std::mem::MaybeUninit<SecondStruct>,
+            __param_0: ::ctor::RvalueReference<'b, SecondStruct>,
+        );
+        pub(crate) fn __rust_thunk___ZN12SecondStructaSEOS_<'a, 'b>(
+            __this: std::pin::Pin<&'a mut SecondStruct>,
+            __param_0: ::ctor::RvalueReference<'b, SecondStruct>,
+        ) -> std::pin::Pin<&'a mut SecondStruct>;
         pub(crate) fn __rust_thunk___Z11second_funcv() -> i32;
     }
 }
@@ -124,6 +194,20 @@ const _: () = assert!(std::mem::size_of::<FirstStruct>() == 4usize);
 const _: () = assert!(std::mem::align_of::<FirstStruct>() == 4usize);
 const _: () = assert!(offset_of!(FirstStruct, field) * 8 == 0usize);
 
+// `FirstStruct` is `repr(C)`, `Copy`, non-`Drop`, has no padding, and no
+// fields with niches that zeroing would invalidate, so it is eligible for
+// zero-copy reinterpretation in both directions.
+unsafe impl ::zerocopy::Immutable for FirstStruct {}
+unsafe impl ::zerocopy::IntoBytes for FirstStruct {}
+unsafe impl ::zerocopy::FromBytes for FirstStruct {}
+
 const _: () = assert!(std::mem::size_of::<SecondStruct>() == 4usize);
 const _: () = assert!(std::mem::align_of::<SecondStruct>() == 4usize);
 const _: () = assert!(offset_of!(SecondStruct, field) * 8 == 0usize);
+
+// `SecondStruct` is `repr(C)`, `Copy`, non-`Drop`, has no padding, and no
+// fields with niches that zeroing would invalidate, so it is eligible for
+// zero-copy reinterpretation in both directions.
+unsafe impl ::zerocopy::Immutable for SecondStruct {}
+unsafe impl ::zerocopy::IntoBytes for SecondStruct {}
+unsafe impl ::zerocopy::FromBytes for SecondStruct {}
@@ -41,13 +41,40 @@ impl From<*const Trivial> for Trivial {
     }
 }
 
-// rs_bindings_from_cc/test/golden/trivial_type.h;l=8
-// Error while generating bindings for item 'Trivial::Trivial':
-// Parameter type 'struct Trivial &&' is not supported
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>> for Trivial {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: std::pin::Pin<&mut std::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN7TrivialC1EOS_(
+                        std::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)> for Trivial {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
 
-// rs_bindings_from_cc/test/golden/trivial_type.h;l=8
-// Error while generating bindings for item 'Trivial::operator=':
-// Parameter type 'struct Trivial &&' is not supported
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>> for Trivial {
+    #[inline(always)]
+    fn assign<'a>(self: std::pin::Pin<&'a mut Self>, __param_0: ::ctor::RvalueReference<'b, Self>) {
+        unsafe {
+            crate::detail::__rust_thunk___ZN7TrivialaSEOS_(self, __param_0);
+        }
+    }
+}
 
 /// Defaulted special member functions are trivial on a struct with only trivial
 /// members.
@@ -68,13 +95,40 @@ impl Default for TrivialWithDefaulted {
     }
 }
 
-// rs_bindings_from_cc/test/golden/trivial_type.h;l=19
-// Error while generating bindings for item 'TrivialWithDefaulted::TrivialWithDefaulted':
-// Parameter type 'struct TrivialWithDefaulted &&' is not supported
+impl<'b> ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>> for TrivialWithDefaulted {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: ::ctor::RvalueReference<'b, Self>) -> Self::CtorType {
+        let __param_0 = args;
+        unsafe {
+            ::ctor::FnCtor::new(
+                move |dest: std::pin::Pin<&mut std::mem::MaybeUninit<Self>>| {
+                    crate::detail::__rust_thunk___ZN20TrivialWithDefaultedC1EOS_(
+                        std::pin::Pin::into_inner_unchecked(dest),
+                        __param_0,
+                    );
+                },
+            )
+        }
+    }
+}
+impl<'b> ::ctor::CtorNew<(::ctor::RvalueReference<'b, Self>,)> for TrivialWithDefaulted {
+    type CtorType = impl ::ctor::Ctor<Output = Self> + ::ctor::Captures<'b>;
+    #[inline(always)]
+    fn ctor_new(args: (::ctor::RvalueReference<'b, Self>,)) -> Self::CtorType {
+        let (arg,) = args;
+        <Self as ::ctor::CtorNew<::ctor::RvalueReference<'b, Self>>>::ctor_new(arg)
+    }
+}
 
-// rs_bindings_from_cc/test/golden/trivial_type.h;l=20
-// Error while generating bindings for item 'TrivialWithDefaulted::operator=':
-// Parameter type 'struct TrivialWithDefaulted &&' is not supported
+impl<'b> ::ctor::Assign<::ctor::RvalueReference<'b, Self>> for TrivialWithDefaulted {
+    #[inline(always)]
+    fn assign<'a>(self: std::pin::Pin<&'a mut Self>, __param_0: ::ctor::RvalueReference<'b, Self>) {
+        unsafe {
+            crate::detail::__rust_thunk___ZN20TrivialWithDefaultedaSEOS_(self, __param_0);
+        }
+    }
+}
 
 /// This struct is trivial, and therefore trivially relocatable etc., but still
 /// not safe to pass by reference as it is not final.
@@ -144,9 +198,25 @@ mod detail {
             __this: &mut std::mem::MaybeUninit<Trivial>,
             __param_0: *const Trivial,
         );
+        pub(crate) fn __rust_thunk___ZN7TrivialC1EOS_<'a, 'b>(
+            __this: &'a mut std::mem::MaybeUninit<Trivial>,
+            __param_0: ::ctor::RvalueReference<'b, Trivial>,
+        );
+        pub(crate) fn __rust_thunk___ZN7TrivialaSEOS_<'a, 'b>(
+            __this: std::pin::Pin<&'a mut Trivial>,
+            __param_0: ::ctor::RvalueReference<'b, Trivial>,
+        ) -> std::pin::Pin<&'a mut Trivial>;
         pub(crate) fn __rust_thunk___ZN20TrivialWithDefaultedC1Ev<'a>(
             __this: &'a mut std::mem::MaybeUninit<TrivialWithDefaulted>,
         );
+        pub(crate) fn __rust_thunk___ZN20TrivialWithDefaultedC1EOS_<'a, 'b>(
+            __this: &'a mut std::mem::MaybeUninit<TrivialWithDefaulted>,
+            __param_0: ::ctor::RvalueReference<'b, TrivialWithDefaulted>,
+        );
+        pub(crate) fn __rust_thunk___ZN20TrivialWithDefaultedaSEOS_<'a, 'b>(
+            __this: std::pin::Pin<&'a mut TrivialWithDefaulted>,
+            __param_0: ::ctor::RvalueReference<'b, TrivialWithDefaulted>,
+        ) -> std::pin::Pin<&'a mut TrivialWithDefaulted>;
         #[link_name = "_Z12TakesByValue7Trivial"]
         pub(crate) fn __rust_thunk___Z12TakesByValue7Trivial(trivial: Trivial);
         #[link_name = "_Z25TakesWithDefaultedByValue20TrivialWithDefaulted"]
@@ -176,10 +246,28 @@ const _: () = assert!(std::mem::size_of::<Trivial>() == 4usize);
 const _: () = assert!(std::mem::align_of::<Trivial>() == 4usize);
 const _: () = assert!(offset_of!(Trivial, trivial_field) * 8 == 0usize);
 
+// `Trivial` is `repr(C)`, `Copy`, non-`Drop`, has no padding, and no fields
+// with niches that zeroing would invalidate, so it is eligible for
+// zero-copy reinterpretation in both directions.
+unsafe impl ::zerocopy::Immutable for Trivial {}
+unsafe impl ::zerocopy::IntoBytes for Trivial {}
+unsafe impl ::zerocopy::FromBytes for Trivial {}
+
 const _: () = assert!(std::mem::size_of::<TrivialWithDefaulted>() == 4usize);
 const _: () = assert!(std::mem::align_of::<TrivialWithDefaulted>() == 4usize);
 const _: () = assert!(offset_of!(TrivialWithDefaulted, trivial_field) * 8 == 0usize);
 
+// `TrivialWithDefaulted` is `repr(C)`, `Copy`, non-`Drop`, has no padding,
+// and no fields with niches that zeroing would invalidate, so it is
+// eligible for zero-copy reinterpretation in both directions.
+unsafe impl ::zerocopy::Immutable for TrivialWithDefaulted {}
+unsafe impl ::zerocopy::IntoBytes for TrivialWithDefaulted {}
+unsafe impl ::zerocopy::FromBytes for TrivialWithDefaulted {}
+
 const _: () = assert!(std::mem::size_of::<TrivialNonfinal>() == 4usize);
 const _: () = assert!(std::mem::align_of::<TrivialNonfinal>() == 4usize);
 const _: () = assert!(offset_of!(TrivialNonfinal, trivial_field) * 8 == 0usize);
+
+// `TrivialNonfinal` is `!Unpin` (address-sensitive), so it is excluded from
+// zero-copy reinterpretation entirely, even though its layout has no
+// padding.